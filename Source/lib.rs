@@ -7,13 +7,15 @@ use std::os::unix::fs::{MetadataExt, PermissionsExt};
 #[cfg(windows)]
 use std::os::windows::fs::MetadataExt;
 use std::{
-	path::PathBuf,
+	path::{Component, Path, PathBuf},
 	time::{SystemTime, UNIX_EPOCH},
 };
 
-use serde::{Serialize, ser::Serializer};
+use serde::{Deserialize, Serialize, ser::Serializer};
 use tauri::{
+	Manager,
 	Runtime,
+	State,
 	command,
 	plugin::{Builder as PluginBuilder, TauriPlugin},
 };
@@ -24,6 +26,84 @@ type Result<T> = std::result::Result<T, Error>;
 pub enum Error {
 	#[error(transparent)]
 	Io(#[from] std::io::Error),
+	#[error(transparent)]
+	Glob(#[from] glob::PatternError),
+	#[error("path not allowed by the fs-extra scope: {0}")]
+	PathNotAllowed(PathBuf),
+}
+
+/// Gates which paths the frontend may query, mirroring Tauri's capability model
+/// where filesystem access is granted by explicit scopes. A path is allowed
+/// when it has no `..` component, matches no `deny` pattern, and either the
+/// `allow` set is empty or it matches one of its patterns.
+#[derive(Default)]
+pub struct Scope {
+	allow:Vec<glob::Pattern>,
+	deny:Vec<glob::Pattern>,
+}
+
+impl Scope {
+	/// Build a scope from `allow` and `deny` glob patterns. An empty `allow`
+	/// set permits every path that is not explicitly denied and has no `..`
+	/// component.
+	pub fn new<A, D, S>(allow:A, deny:D) -> Result<Self>
+	where
+		A: IntoIterator<Item = S>,
+		D: IntoIterator<Item = S>,
+		S: AsRef<str>, {
+		Ok(Self {
+			allow:allow
+				.into_iter()
+				.map(|pattern| glob::Pattern::new(pattern.as_ref()))
+				.collect::<std::result::Result<_, _>>()?,
+			deny:deny
+				.into_iter()
+				.map(|pattern| glob::Pattern::new(pattern.as_ref()))
+				.collect::<std::result::Result<_, _>>()?,
+		})
+	}
+
+	/// Lexically normalize `path`, rejecting any `..` component so a request
+	/// cannot escape an allowed root via traversal. `.` components are dropped.
+	fn normalize(path:&Path) -> Option<PathBuf> {
+		let mut normalized = PathBuf::new();
+		for component in path.components() {
+			match component {
+				Component::ParentDir => return None,
+				Component::CurDir => {},
+				other => normalized.push(other.as_os_str()),
+			}
+		}
+		Some(normalized)
+	}
+
+	/// Return the normalized form of `path` when it passes the scope, or `None`
+	/// when it traverses outside, matches a `deny` pattern, or is not covered by
+	/// a non-empty `allow` set. Matching is done against the normalized path so
+	/// it agrees with what is later handed to the filesystem.
+	fn normalized_if_allowed(&self, path:&Path) -> Option<PathBuf> {
+		let normalized = Self::normalize(path)?;
+
+		let as_str = normalized.to_string_lossy();
+
+		if self.deny.iter().any(|pattern| pattern.matches(&as_str)) {
+			return None;
+		}
+
+		if self.allow.is_empty() || self.allow.iter().any(|pattern| pattern.matches(&as_str)) {
+			Some(normalized)
+		} else {
+			None
+		}
+	}
+
+	fn is_allowed(&self, path:&Path) -> bool { self.normalized_if_allowed(path).is_some() }
+
+	/// Check `path` against the scope, returning the normalized path to stat or
+	/// [`Error::PathNotAllowed`] when it falls outside the allowed set.
+	fn check(&self, path:&Path) -> Result<PathBuf> {
+		self.normalized_if_allowed(path).ok_or_else(|| Error::PathNotAllowed(path.to_path_buf()))
+	}
 }
 
 impl Serialize for Error {
@@ -60,14 +140,18 @@ struct UnixMetadata {
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 struct Metadata {
-	accessed_at_ms:u64,
-	created_at_ms:u64,
-	modified_at_ms:u64,
+	accessed_at_ms:Option<u64>,
+	created_at_ms:Option<u64>,
+	modified_at_ms:Option<u64>,
 	is_dir:bool,
 	is_file:bool,
 	is_symlink:bool,
 	size:u64,
 	permissions:Permissions,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	canonicalized_path:Option<PathBuf>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	mime:Option<String>,
 	#[cfg(unix)]
 	#[serde(flatten)]
 	unix:UnixMetadata,
@@ -75,24 +159,60 @@ struct Metadata {
 	file_attributes:u32,
 }
 
-fn system_time_to_ms(time:std::io::Result<SystemTime>) -> u64 {
-	time.map(|time| {
+fn system_time_to_ms(time:std::io::Result<SystemTime>) -> Option<u64> {
+	time.ok().map(|time| {
 		time.duration_since(UNIX_EPOCH)
 			.map(|t| t.as_millis() as u64)
 			.unwrap_or_else(|err| err.duration().as_millis() as u64)
 	})
-	.unwrap_or_default()
 }
 
-#[command]
-async fn metadata(path:PathBuf) -> Result<Metadata> {
-	let metadata = std::fs::metadata(path)?;
+/// Guess the MIME type of `path` from its extension, falling back to a light
+/// content sniff for a handful of common formats when the file has no useful
+/// extension.
+fn guess_mime(path:&Path) -> Option<String> {
+	if let Some(guess) = mime_guess::from_path(path).first() {
+		return Some(guess.essence_str().to_string());
+	}
+
+	sniff_mime(path)
+}
+
+fn sniff_mime(path:&Path) -> Option<String> {
+	use std::io::Read;
+
+	let mut file = std::fs::File::open(path).ok()?;
+	let mut buf = [0u8; 16];
+	let read = file.read(&mut buf).ok()?;
+	let buf = &buf[..read];
+
+	let mime = if buf.starts_with(b"\x89PNG\r\n\x1a\n") {
+		"image/png"
+	} else if buf.starts_with(&[0xff, 0xd8, 0xff]) {
+		"image/jpeg"
+	} else if buf.starts_with(b"GIF87a") || buf.starts_with(b"GIF89a") {
+		"image/gif"
+	} else if buf.starts_with(b"%PDF-") {
+		"application/pdf"
+	} else if buf.starts_with(b"\x7fELF") {
+		"application/x-executable"
+	} else {
+		return None;
+	};
+
+	Some(mime.to_string())
+}
 
+fn build_metadata(
+	metadata:std::fs::Metadata,
+	canonicalized_path:Option<PathBuf>,
+	mime:Option<String>,
+) -> Metadata {
 	let file_type = metadata.file_type();
 
 	let permissions = metadata.permissions();
 
-	Ok(Metadata {
+	Metadata {
 		accessed_at_ms:system_time_to_ms(metadata.accessed()),
 		created_at_ms:system_time_to_ms(metadata.created()),
 		modified_at_ms:system_time_to_ms(metadata.modified()),
@@ -105,6 +225,8 @@ async fn metadata(path:PathBuf) -> Result<Metadata> {
 			#[cfg(unix)]
 			mode:permissions.mode(),
 		},
+		canonicalized_path,
+		mime,
 		#[cfg(unix)]
 		unix:UnixMetadata {
 			dev:metadata.dev(),
@@ -119,14 +241,273 @@ async fn metadata(path:PathBuf) -> Result<Metadata> {
 		},
 		#[cfg(windows)]
 		file_attributes:metadata.file_attributes(),
-	})
+	}
 }
 
 #[command]
-async fn exists(path:PathBuf) -> bool { path.exists() }
+async fn metadata(
+	path:PathBuf,
+	scope:State<'_, Scope>,
+	follow_symlinks:Option<bool>,
+	resolve:Option<bool>,
+	detect_mime:Option<bool>,
+) -> Result<Metadata> {
+	let path = scope.check(&path)?;
+
+	let metadata = if follow_symlinks.unwrap_or(true) {
+		std::fs::metadata(&path)?
+	} else {
+		std::fs::symlink_metadata(&path)?
+	};
+
+	// Best-effort: a dangling symlink can be lstat'd even though it cannot be
+	// resolved, so a canonicalize failure leaves the field `None` rather than
+	// failing the whole request.
+	let canonicalized_path =
+		if resolve.unwrap_or(false) { std::fs::canonicalize(&path).ok() } else { None };
+
+	let mime = if detect_mime.unwrap_or(false) { guess_mime(&path) } else { None };
+
+	Ok(build_metadata(metadata, canonicalized_path, mime))
+}
+
+#[command]
+async fn metadata_batch(
+	paths:Vec<PathBuf>,
+	scope:State<'_, Scope>,
+) -> Result<Vec<std::result::Result<Metadata, String>>> {
+	let mut pending = Vec::with_capacity(paths.len());
+	for path in paths {
+		match scope.check(&path) {
+			Ok(path) => {
+				pending.push(Ok(tauri::async_runtime::spawn_blocking(move || {
+					std::fs::metadata(path)
+						.map(|metadata| build_metadata(metadata, None, None))
+						.map_err(|err| err.to_string())
+				})));
+			},
+			Err(err) => pending.push(Err(err.to_string())),
+		}
+	}
+
+	let mut results = Vec::with_capacity(pending.len());
+	for entry in pending {
+		match entry {
+			Ok(handle) => results.push(handle.await.unwrap_or_else(|err| Err(err.to_string()))),
+			Err(err) => results.push(Err(err)),
+		}
+	}
+	Ok(results)
+}
+
+/// Files at or below this size are always hashed in full, even when a partial
+/// checksum is requested.
+const PARTIAL_CHECKSUM_THRESHOLD:u64 = 100 * 1024;
+
+/// Size of each window sampled from the start, middle and end of a large file
+/// when computing a partial checksum.
+const CHECKSUM_SAMPLE_WINDOW:usize = 16 * 1024;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum HashAlgorithm {
+	Blake3,
+	Sha256,
+}
+
+fn to_hex(bytes:&[u8]) -> String {
+	use std::fmt::Write;
+
+	let mut out = String::with_capacity(bytes.len() * 2);
+	for byte in bytes {
+		let _ = write!(out, "{byte:02x}");
+	}
+	out
+}
+
+fn hash_file(mut file:std::fs::File, len:u64, full:bool, mut update:impl FnMut(&[u8])) -> Result<()> {
+	use std::io::{Read, Seek, SeekFrom};
+
+	if full || len <= PARTIAL_CHECKSUM_THRESHOLD {
+		let mut buf = [0u8; 64 * 1024];
+		loop {
+			let read = file.read(&mut buf)?;
+			if read == 0 {
+				break;
+			}
+			update(&buf[..read]);
+		}
+	} else {
+		let window = CHECKSUM_SAMPLE_WINDOW as u64;
+		let offsets = [0, len / 2 - window / 2, len - window];
+
+		let mut buf = vec![0u8; CHECKSUM_SAMPLE_WINDOW];
+		for offset in offsets {
+			file.seek(SeekFrom::Start(offset))?;
+			file.read_exact(&mut buf)?;
+			update(&buf);
+		}
 
-pub fn init<R:Runtime>() -> TauriPlugin<R> {
+		// Mix the length in so files that share sampled windows but differ in
+		// size do not collide.
+		update(&len.to_le_bytes());
+	}
+
+	Ok(())
+}
+
+fn checksum_inner(path:&Path, algorithm:HashAlgorithm, full:bool) -> Result<String> {
+	let file = std::fs::File::open(path)?;
+	let len = file.metadata()?.len();
+
+	match algorithm {
+		HashAlgorithm::Blake3 => {
+			let mut hasher = blake3::Hasher::new();
+			hash_file(file, len, full, |buf| {
+				hasher.update(buf);
+			})?;
+			Ok(hasher.finalize().to_hex().to_string())
+		},
+		HashAlgorithm::Sha256 => {
+			use sha2::{Digest, Sha256};
+
+			let mut hasher = Sha256::new();
+			hash_file(file, len, full, |buf| {
+				hasher.update(buf);
+			})?;
+			Ok(to_hex(&hasher.finalize()))
+		},
+	}
+}
+
+#[command]
+async fn checksum(
+	path:PathBuf,
+	scope:State<'_, Scope>,
+	algorithm:HashAlgorithm,
+	full:bool,
+) -> Result<String> {
+	let path = scope.check(&path)?;
+
+	tauri::async_runtime::spawn_blocking(move || checksum_inner(&path, algorithm, full))
+		.await
+		.map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?
+}
+
+#[command]
+async fn mime_type(path:PathBuf, scope:State<'_, Scope>) -> Result<Option<String>> {
+	let path = scope.check(&path)?;
+
+	Ok(guess_mime(&path))
+}
+
+#[command]
+async fn exists(path:PathBuf, scope:State<'_, Scope>) -> Result<bool> {
+	let path = scope.check(&path)?;
+
+	Ok(path.exists())
+}
+
+/// Initialize the plugin with no allow or deny patterns. Equivalent to
+/// [`init_with_scope`] with an empty [`Scope`], which still rejects paths
+/// containing a `..` component.
+pub fn init<R:Runtime>() -> TauriPlugin<R> { init_with_scope(Scope::default()) }
+
+/// Initialize the plugin, gating every path-taking command behind `scope`.
+pub fn init_with_scope<R:Runtime>(scope:Scope) -> TauriPlugin<R> {
 	PluginBuilder::new("fs-extra")
-		.invoke_handler(tauri::generate_handler![exists, metadata])
+		.invoke_handler(tauri::generate_handler![
+			exists,
+			metadata,
+			metadata_batch,
+			checksum,
+			mime_type
+		])
+		.setup(move |app, _api| {
+			app.manage(scope);
+
+			Ok(())
+		})
 		.build()
 }
+
+#[cfg(test)]
+mod tests {
+	use std::io::Write;
+
+	use super::*;
+
+	#[test]
+	fn scope_empty_allow_permits_everything() {
+		let scope = Scope::default();
+		assert!(scope.is_allowed(Path::new("/anywhere/at/all")));
+	}
+
+	#[test]
+	fn scope_allow_and_deny_patterns() {
+		let scope = Scope::new(["/allowed/**"], ["/allowed/secret/**"]).unwrap();
+		assert!(scope.is_allowed(Path::new("/allowed/file.txt")));
+		assert!(!scope.is_allowed(Path::new("/allowed/secret/key")));
+		assert!(!scope.is_allowed(Path::new("/elsewhere/file.txt")));
+	}
+
+	#[test]
+	fn scope_rejects_parent_dir_traversal() {
+		let scope = Scope::new(["/allowed/**"], Vec::<&str>::new()).unwrap();
+		// Matches the allow glob textually but escapes the root via `..`.
+		assert!(!scope.is_allowed(Path::new("/allowed/../etc/shadow")));
+	}
+
+	#[test]
+	fn scope_check_returns_normalized_path() {
+		let scope = Scope::default();
+		assert_eq!(scope.check(Path::new("/a/./b")).unwrap(), PathBuf::from("/a/b"));
+		assert!(scope.check(Path::new("/a/../b")).is_err());
+	}
+
+	#[test]
+	fn hex_encodes_bytes_lower_padded() {
+		assert_eq!(to_hex(&[0x00, 0x0f, 0xff, 0xa5]), "000fffa5");
+		assert_eq!(to_hex(&[]), "");
+	}
+
+	fn temp_file(name:&str, len:usize) -> PathBuf {
+		let path = std::env::temp_dir().join(format!("fs-extra-{name}"));
+
+		let mut file = std::fs::File::create(&path).unwrap();
+		file.write_all(&vec![b'x'; len]).unwrap();
+
+		path
+	}
+
+	fn blake3_of(path:&Path, full:bool) -> String {
+		let file = std::fs::File::open(path).unwrap();
+		let len = file.metadata().unwrap().len();
+
+		let mut hasher = blake3::Hasher::new();
+		hash_file(file, len, full, |buf| {
+			hasher.update(buf);
+		})
+		.unwrap();
+
+		hasher.finalize().to_hex().to_string()
+	}
+
+	#[test]
+	fn hash_file_small_files_ignore_partial_flag() {
+		// At or below the threshold the whole file is always read, so `full`
+		// makes no difference.
+		let path = temp_file("small", PARTIAL_CHECKSUM_THRESHOLD as usize);
+		assert_eq!(blake3_of(&path, false), blake3_of(&path, true));
+		std::fs::remove_file(&path).unwrap();
+	}
+
+	#[test]
+	fn hash_file_large_files_sample_when_not_full() {
+		// Just over the threshold the partial branch samples windows, which
+		// must differ from a full read of the same file.
+		let path = temp_file("large", PARTIAL_CHECKSUM_THRESHOLD as usize + 1);
+		assert_ne!(blake3_of(&path, false), blake3_of(&path, true));
+		std::fs::remove_file(&path).unwrap();
+	}
+}